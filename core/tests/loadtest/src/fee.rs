@@ -0,0 +1,162 @@
+// Built-in import
+use std::sync::Mutex;
+// External uses
+use num::BigUint;
+// Workspace uses
+use zksync::utils::closest_packable_fee_amount;
+
+/// Computes the fee to attach to a transaction given the network's latest fee quote.
+/// A [`TestWallet`] holds a boxed strategy so scenarios can swap it out to deliberately
+/// test under-funded or aggressively-priced transactions.
+///
+/// [`TestWallet`]: crate::test_wallet::TestWallet
+pub trait FeeStrategy: Send + Sync {
+    /// Returns the packable fee to use, given the most recent `total_fee` quote.
+    fn compute_fee(&self, quoted_fee: &BigUint) -> BigUint;
+
+    /// A short, human-readable name for this strategy, used in `Debug` output.
+    fn name(&self) -> &'static str;
+}
+
+/// Multiplies the network quote by a fixed factor. This is the historical, default
+/// behavior of `TestWallet::sufficient_fee`.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedMultiplierStrategy {
+    factor: u64,
+}
+
+impl FixedMultiplierStrategy {
+    pub fn new(factor: u64) -> Self {
+        Self { factor }
+    }
+}
+
+impl Default for FixedMultiplierStrategy {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+impl FeeStrategy for FixedMultiplierStrategy {
+    fn compute_fee(&self, quoted_fee: &BigUint) -> BigUint {
+        closest_packable_fee_amount(&(quoted_fee * BigUint::from(self.factor)))
+    }
+
+    fn name(&self) -> &'static str {
+        "FixedMultiplierStrategy"
+    }
+}
+
+/// Returns exactly the quoted fee, with no padding. Useful for tests that want to
+/// exercise the minimum fee the network will accept.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MinViableFeeStrategy;
+
+impl FeeStrategy for MinViableFeeStrategy {
+    fn compute_fee(&self, quoted_fee: &BigUint) -> BigUint {
+        closest_packable_fee_amount(quoted_fee)
+    }
+
+    fn name(&self) -> &'static str {
+        "MinViableFeeStrategy"
+    }
+}
+
+/// Tracks an exponential moving average of recent fee quotes and uses that average
+/// instead of reacting to the latest quote alone, smoothing over short-lived
+/// congestion spikes.
+#[derive(Debug)]
+pub struct EmaFeeStrategy {
+    /// Weight given to the newest quote, in the range `(0.0, 1.0]`.
+    alpha: f64,
+    average: Mutex<Option<BigUint>>,
+}
+
+impl EmaFeeStrategy {
+    pub fn new(alpha: f64) -> Self {
+        Self {
+            alpha,
+            average: Mutex::new(None),
+        }
+    }
+}
+
+impl Default for EmaFeeStrategy {
+    fn default() -> Self {
+        Self::new(0.5)
+    }
+}
+
+impl FeeStrategy for EmaFeeStrategy {
+    fn compute_fee(&self, quoted_fee: &BigUint) -> BigUint {
+        let mut average = self.average.lock().unwrap();
+        let updated = match average.as_ref() {
+            Some(prev) => weighted_average(prev, quoted_fee, self.alpha),
+            None => quoted_fee.clone(),
+        };
+        *average = Some(updated.clone());
+
+        closest_packable_fee_amount(&updated)
+    }
+
+    fn name(&self) -> &'static str {
+        "EmaFeeStrategy"
+    }
+}
+
+/// Computes `alpha * quote + (1 - alpha) * prev` using fixed-point integer arithmetic,
+/// since `BigUint` has no floating-point operations.
+fn weighted_average(prev: &BigUint, quote: &BigUint, alpha: f64) -> BigUint {
+    const SCALE: u64 = 1_000;
+    let weight = ((alpha * SCALE as f64).round() as u64).min(SCALE);
+
+    (quote * BigUint::from(weight) + prev * BigUint::from(SCALE - weight)) / BigUint::from(SCALE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weighted_average_with_equal_weight_is_the_midpoint() {
+        let average = weighted_average(&BigUint::from(100u32), &BigUint::from(200u32), 0.5);
+        assert_eq!(average, BigUint::from(150u32));
+    }
+
+    #[test]
+    fn weighted_average_clamps_alpha_above_one_to_the_quote() {
+        let average = weighted_average(&BigUint::from(100u32), &BigUint::from(200u32), 1.5);
+        assert_eq!(average, BigUint::from(200u32));
+    }
+
+    #[test]
+    fn ema_strategy_seeds_from_the_first_quote() {
+        let strategy = EmaFeeStrategy::new(0.5);
+        let fee = strategy.compute_fee(&BigUint::from(100u32));
+        assert_eq!(fee, closest_packable_fee_amount(&BigUint::from(100u32)));
+    }
+
+    #[test]
+    fn ema_strategy_smooths_a_later_spike() {
+        let strategy = EmaFeeStrategy::new(0.5);
+        strategy.compute_fee(&BigUint::from(100u32));
+        let fee = strategy.compute_fee(&BigUint::from(300u32));
+
+        // Halfway between the seeded 100 and the spiking 300 quote, then packed.
+        assert_eq!(fee, closest_packable_fee_amount(&BigUint::from(200u32)));
+    }
+
+    #[test]
+    fn min_viable_strategy_returns_exactly_the_quote() {
+        let strategy = MinViableFeeStrategy;
+        let fee = strategy.compute_fee(&BigUint::from(321u32));
+        assert_eq!(fee, closest_packable_fee_amount(&BigUint::from(321u32)));
+    }
+
+    #[test]
+    fn fixed_multiplier_strategy_multiplies_the_quote() {
+        let strategy = FixedMultiplierStrategy::new(3);
+        let fee = strategy.compute_fee(&BigUint::from(10u32));
+        assert_eq!(fee, closest_packable_fee_amount(&BigUint::from(30u32)));
+    }
+}
@@ -0,0 +1,137 @@
+// Built-in import
+use std::ops::Range;
+// External uses
+use anyhow::{ensure, Context};
+use bip39::{Language, Mnemonic, Seed};
+use hmac::{Hmac, Mac, NewMac};
+use secp256k1::SecretKey;
+use sha2::Sha512;
+// Workspace uses
+use zksync::web3::types::H256;
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// One step of a BIP32 extended private key, holding just enough state to derive
+/// its children along the standard Ethereum derivation path.
+struct ExtendedKey {
+    secret_key: SecretKey,
+    chain_code: [u8; 32],
+}
+
+impl ExtendedKey {
+    /// Derives the BIP32 master key from a BIP39 seed.
+    fn master(seed: &[u8]) -> anyhow::Result<Self> {
+        let mut mac =
+            HmacSha512::new_from_slice(b"Bitcoin seed").expect("HMAC accepts any key size");
+        mac.update(seed);
+        let digest = mac.finalize().into_bytes();
+        let (secret_key, chain_code) = digest.split_at(32);
+
+        Ok(Self {
+            secret_key: SecretKey::from_slice(secret_key).context("invalid master key")?,
+            chain_code: chain_code.try_into().unwrap(),
+        })
+    }
+
+    /// Derives the child key at `index`, hardened if `hardened` is set.
+    fn derive_child(&self, index: u32, hardened: bool) -> anyhow::Result<Self> {
+        let index = if hardened { index | 0x8000_0000 } else { index };
+
+        let mut mac =
+            HmacSha512::new_from_slice(&self.chain_code).expect("HMAC accepts any key size");
+        if hardened {
+            mac.update(&[0u8]);
+            mac.update(&self.secret_key[..]);
+        } else {
+            let secp = secp256k1::Secp256k1::signing_only();
+            let public_key = secp256k1::PublicKey::from_secret_key(&secp, &self.secret_key);
+            mac.update(&public_key.serialize());
+        }
+        mac.update(&index.to_be_bytes());
+
+        let digest = mac.finalize().into_bytes();
+        let (tweak, chain_code) = digest.split_at(32);
+
+        let mut secret_key = SecretKey::from_slice(tweak).context("invalid derived key")?;
+        secret_key
+            .add_assign(&self.secret_key[..])
+            .context("child key derivation failed")?;
+
+        Ok(Self {
+            secret_key,
+            chain_code: chain_code.try_into().unwrap(),
+        })
+    }
+}
+
+/// Derives the Ethereum private key for `account_index` from `mnemonic`, following the
+/// standard `m/44'/60'/0'/0/{index}` path. The same mnemonic and index always yield the
+/// same key, so a scenario can specify one seed phrase and have the same addresses
+/// regenerated every run.
+pub fn derive_eth_private_key(mnemonic: &str, account_index: u32) -> anyhow::Result<H256> {
+    let mnemonic = Mnemonic::from_phrase(mnemonic, Language::English)
+        .map_err(|err| anyhow::anyhow!("invalid mnemonic: {}", err))?;
+    ensure!(
+        mnemonic.word_count() >= 12,
+        "mnemonic must have at least 12 words"
+    );
+    let seed = Seed::new(&mnemonic, "");
+
+    let key = ExtendedKey::master(seed.as_bytes())?
+        .derive_child(44, true)?
+        .derive_child(60, true)?
+        .derive_child(0, true)?
+        .derive_child(0, false)?
+        .derive_child(account_index, false)?;
+
+    Ok(H256::from_slice(&key.secret_key[..]))
+}
+
+/// Derives the Ethereum private keys for every index in `range` from a single `mnemonic`.
+pub fn derive_eth_private_keys(mnemonic: &str, range: Range<u32>) -> anyhow::Result<Vec<H256>> {
+    range
+        .map(|account_index| derive_eth_private_key(mnemonic, account_index))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::tx::PackedEthSignature;
+
+    // The well-known Hardhat/ganache test mnemonic, whose first few accounts and
+    // private keys are publicly documented, giving us a real test vector rather than
+    // just round-tripping our own derivation against itself.
+    const TEST_MNEMONIC: &str = "test test test test test test test test test test test junk";
+
+    #[test]
+    fn derives_known_test_vector() {
+        let private_key = derive_eth_private_key(TEST_MNEMONIC, 0).unwrap();
+        let expected_private_key = H256::from_slice(
+            &hex::decode("ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80")
+                .unwrap(),
+        );
+        assert_eq!(private_key, expected_private_key);
+
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let expected_address =
+            hex::decode("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266").unwrap();
+        assert_eq!(address.as_bytes(), expected_address.as_slice());
+    }
+
+    #[test]
+    fn derive_eth_private_keys_matches_single_derivation() {
+        let batch = derive_eth_private_keys(TEST_MNEMONIC, 0..3).unwrap();
+
+        assert_eq!(batch.len(), 3);
+        for (index, key) in batch.iter().enumerate() {
+            let expected = derive_eth_private_key(TEST_MNEMONIC, index as u32).unwrap();
+            assert_eq!(*key, expected);
+        }
+    }
+
+    #[test]
+    fn rejects_too_short_mnemonics() {
+        assert!(derive_eth_private_key("test test test", 0).is_err());
+    }
+}
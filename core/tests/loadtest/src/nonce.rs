@@ -0,0 +1,200 @@
+// Built-in import
+use std::{
+    collections::BTreeSet,
+    ops::Range,
+    sync::atomic::{AtomicU32, Ordering},
+};
+// External uses
+use tokio::sync::{Mutex, OnceCell};
+// Workspace uses
+use zksync::{error::ClientError, provider::Provider, RpcProvider};
+use zksync_types::{Address, Nonce};
+
+/// Tracks the local nonce of a [`TestWallet`](crate::test_wallet::TestWallet) and keeps
+/// it in sync with the network.
+///
+/// The manager lazily initializes its counter from the committed account nonce on first
+/// use, hands out sequential nonces to callers, and lets a failed submission return its
+/// reserved nonce to a free-list instead of leaving a permanent gap.
+#[derive(Debug, Default)]
+pub struct NonceManager {
+    initialized: OnceCell<()>,
+    nonce: AtomicU32,
+    /// Nonces that were reserved for a transaction which did not end up being sent,
+    /// handed out again before the counter advances any further.
+    free_nonces: Mutex<BTreeSet<u32>>,
+}
+
+impl NonceManager {
+    /// Makes sure the local counter has been seeded from the network. Concurrent
+    /// callers race to run the initializer only through `OnceCell`, so exactly one of
+    /// them performs the network read and `store`, instead of each observing an
+    /// uninitialized counter and clobbering whatever the others already handed out.
+    async fn ensure_initialized(
+        &self,
+        provider: &RpcProvider,
+        address: Address,
+    ) -> Result<(), ClientError> {
+        self.initialized
+            .get_or_try_init(|| async {
+                let committed_nonce = provider.account_info(address).await?.committed.nonce;
+                self.nonce.store(*committed_nonce, Ordering::SeqCst);
+                Ok::<(), ClientError>(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Reserves and returns the next nonce to use for a transaction, preferring a
+    /// previously returned (unused) nonce over advancing the counter.
+    pub async fn next_nonce(
+        &self,
+        provider: &RpcProvider,
+        address: Address,
+    ) -> Result<Nonce, ClientError> {
+        self.ensure_initialized(provider, address).await?;
+
+        if let Some(nonce) = self.free_nonces.lock().await.pop_first() {
+            return Ok(Nonce(nonce));
+        }
+
+        Ok(Nonce(self.nonce.fetch_add(1, Ordering::SeqCst)))
+    }
+
+    /// Returns a reserved nonce to the free-list, e.g. after its transaction failed
+    /// to be sent and the nonce should be reused rather than skipped.
+    pub async fn return_nonce(&self, nonce: Nonce) {
+        self.free_nonces.lock().await.insert(*nonce);
+    }
+
+    /// Atomically reserves `count` contiguous nonces in a single `fetch_add`, e.g. for
+    /// signing a batch of transactions without round-tripping the counter per item.
+    pub async fn reserve_range(
+        &self,
+        provider: &RpcProvider,
+        address: Address,
+        count: u32,
+    ) -> Result<Range<u32>, ClientError> {
+        self.ensure_initialized(provider, address).await?;
+
+        let start = self.nonce.fetch_add(count, Ordering::SeqCst);
+        Ok(start..start + count)
+    }
+
+    /// Returns every nonce in `range` to the free-list, e.g. to roll back the unused
+    /// tail of a reserved range after a mid-batch submission failure.
+    pub async fn return_range(&self, range: Range<u32>) {
+        self.free_nonces.lock().await.extend(range);
+    }
+
+    /// Hard-sets the local counter to `nonce`, discarding any nonces that were queued
+    /// on the free-list, without consulting the network.
+    pub async fn set(&self, nonce: Nonce) {
+        self.nonce.store(*nonce, Ordering::SeqCst);
+        self.free_nonces.lock().await.clear();
+        let _ = self.initialized.set(());
+    }
+
+    /// Re-fetches the committed nonce from the network and resets the local counter,
+    /// discarding any nonces that were queued on the free-list.
+    pub async fn reset(&self, provider: &RpcProvider, address: Address) -> Result<(), ClientError> {
+        let committed_nonce = provider.account_info(address).await?.committed.nonce;
+        self.nonce.store(*committed_nonce, Ordering::SeqCst);
+        self.free_nonces.lock().await.clear();
+        let _ = self.initialized.set(());
+        Ok(())
+    }
+
+    /// Returns whether the given error indicates that the nonce used for a submitted
+    /// transaction is specifically stale (too low or already used), as opposed to some
+    /// other, unrelated validation error that merely mentions "nonce".
+    pub fn is_stale_nonce_error(err: &ClientError) -> bool {
+        let message = err.to_string().to_lowercase();
+        message.contains("nonce")
+            && (message.contains("too low") || message.contains("already used"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nonce_manager_at(start: u32) -> NonceManager {
+        let manager = NonceManager::default();
+        manager.nonce.store(start, Ordering::SeqCst);
+        manager.initialized.set(()).unwrap();
+        manager
+    }
+
+    #[tokio::test]
+    async fn next_nonce_advances_sequentially_once_initialized() {
+        let manager = nonce_manager_at(5);
+
+        // No network access needed: `next_nonce_local`/`reserve_range_local` repeat
+        // `next_nonce`/`reserve_range` without their `ensure_initialized` step, which
+        // `nonce_manager_at` has already satisfied.
+        assert_eq!(*manager.next_nonce_local().await, 5);
+        assert_eq!(*manager.next_nonce_local().await, 6);
+        assert_eq!(*manager.next_nonce_local().await, 7);
+    }
+
+    #[tokio::test]
+    async fn returned_nonce_is_handed_out_before_the_counter_advances() {
+        let manager = nonce_manager_at(10);
+
+        assert_eq!(*manager.next_nonce_local().await, 10);
+        let second = manager.next_nonce_local().await;
+        assert_eq!(*second, 11);
+
+        manager.return_nonce(second).await;
+
+        // The returned nonce comes back before the counter advances to 12.
+        assert_eq!(*manager.next_nonce_local().await, 11);
+        assert_eq!(*manager.next_nonce_local().await, 12);
+    }
+
+    #[tokio::test]
+    async fn reserve_range_is_contiguous_and_return_range_reuses_it() {
+        let manager = nonce_manager_at(0);
+
+        assert_eq!(*manager.next_nonce_local().await, 0);
+        let range = manager.reserve_range_local(3).await;
+        assert_eq!(range, 1..4);
+        assert_eq!(*manager.next_nonce_local().await, 4);
+
+        manager.return_range(range).await;
+        assert_eq!(*manager.next_nonce_local().await, 1);
+        assert_eq!(*manager.next_nonce_local().await, 2);
+        assert_eq!(*manager.next_nonce_local().await, 3);
+    }
+
+    #[test]
+    fn is_stale_nonce_error_matches_only_the_specific_condition() {
+        assert!(NonceManager::is_stale_nonce_error(&ClientError::NetworkError(
+            "Tx nonce is too low.".to_string()
+        )));
+        assert!(NonceManager::is_stale_nonce_error(&ClientError::NetworkError(
+            "Nonce has been already used".to_string()
+        )));
+        assert!(!NonceManager::is_stale_nonce_error(&ClientError::NetworkError(
+            "nonce must be a positive integer".to_string()
+        )));
+        assert!(!NonceManager::is_stale_nonce_error(&ClientError::UnknownToken));
+    }
+
+    impl NonceManager {
+        /// Test-only helper that skips the network round-trip by assuming
+        /// `ensure_initialized` has already run (see `nonce_manager_at`).
+        async fn next_nonce_local(&self) -> Nonce {
+            if let Some(nonce) = self.free_nonces.lock().await.pop_first() {
+                return Nonce(nonce);
+            }
+            Nonce(self.nonce.fetch_add(1, Ordering::SeqCst))
+        }
+
+        async fn reserve_range_local(&self, count: u32) -> Range<u32> {
+            let start = self.nonce.fetch_add(count, Ordering::SeqCst);
+            start..start + count
+        }
+    }
+}
@@ -1,6 +1,7 @@
 // Built-in import
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::ops::Range;
 // External uses
+use futures::future;
 use num::BigUint;
 // Workspace uses
 use zksync::{
@@ -8,34 +9,57 @@ use zksync::{
     ethereum::ierc20_contract,
     provider::Provider,
     types::BlockStatus,
-    utils::{biguint_to_u256, closest_packable_fee_amount, u256_to_biguint},
+    utils::{biguint_to_u256, u256_to_biguint},
     web3::{
         contract::{Contract, Options},
         types::H256,
     },
     EthereumProvider, Network, RpcProvider, Wallet, WalletCredentials,
 };
-use zksync_eth_signer::PrivateKeySigner;
+use zksync_eth_signer::{EthereumSigner, PrivateKeySigner};
 use zksync_types::{
-    tx::PackedEthSignature, AccountId, Address, Nonce, PriorityOp, TokenLike, TxFeeTypes, ZkSyncTx,
+    tx::PackedEthSignature, AccountId, Address, Nonce, PriorityOp, TokenLike, TxFeeTypes, TxHash,
+    ZkSyncTx,
 };
 // Local uses
-use crate::{config::AccountInfo, monitor::Monitor, session::save_wallet};
+use crate::{
+    config::AccountInfo,
+    fee::{FeeStrategy, FixedMultiplierStrategy},
+    mnemonic::derive_eth_private_key,
+    monitor::Monitor,
+    nonce::NonceManager,
+    session::save_wallet,
+};
 
 /// A wrapper over `zksync::Wallet` to make testing more convenient.
-#[derive(Debug)]
-pub struct TestWallet {
+///
+/// Generic over the signer so that a hardware-backed signer (e.g. a Ledger) can drive
+/// the wallet the same way an in-memory private key does; `PrivateKeySigner` remains
+/// the default used by `from_info`/`new_random`.
+pub struct TestWallet<S: EthereumSigner> {
     monitor: Monitor,
-    eth_provider: EthereumProvider<PrivateKeySigner>,
-    inner: Wallet<PrivateKeySigner, RpcProvider>,
+    eth_provider: EthereumProvider<S>,
+    inner: Wallet<S, RpcProvider>,
     token_name: TokenLike,
 
-    nonce: AtomicU32,
+    nonce: NonceManager,
+    fee_strategy: Box<dyn FeeStrategy>,
 }
 
-impl TestWallet {
-    const FEE_FACTOR: u64 = 3;
+impl<S: EthereumSigner> std::fmt::Debug for TestWallet<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestWallet")
+            .field("monitor", &self.monitor)
+            .field("eth_provider", &self.eth_provider)
+            .field("inner", &self.inner)
+            .field("token_name", &self.token_name)
+            .field("nonce", &self.nonce)
+            .field("fee_strategy", &self.fee_strategy.name())
+            .finish()
+    }
+}
 
+impl TestWallet<PrivateKeySigner> {
     /// Creates a new wallet from the given account information and Ethereum configuration options.
     pub async fn from_info(monitor: Monitor, info: &AccountInfo, web3_url: &str) -> Self {
         let credentials = WalletCredentials::from_eth_signer(
@@ -70,20 +94,84 @@ impl TestWallet {
         Self::from_info(monitor, &info, web3_url).await
     }
 
+    /// Creates a wallet whose Ethereum private key is deterministically derived from
+    /// `mnemonic` at `account_index` along the standard `m/44'/60'/0'/0/{index}` path.
+    /// Unlike `new_random`, the same mnemonic and index always yield the same address,
+    /// so pre-funded accounts can be reused across runs.
+    ///
+    /// This still goes through `from_info`/`save_wallet`, which persists the full
+    /// derived `AccountInfo` (including the raw private key) exactly as `new_random`
+    /// does; teaching `session::save_wallet` to persist just the mnemonic index is out
+    /// of scope here and left for a follow-up.
+    pub async fn from_mnemonic(
+        mnemonic: &str,
+        account_index: u32,
+        token_name: TokenLike,
+        monitor: Monitor,
+        web3_url: &str,
+    ) -> anyhow::Result<Self> {
+        let eth_private_key = derive_eth_private_key(mnemonic, account_index)?;
+        let address = PackedEthSignature::address_from_private_key(&eth_private_key)?;
+
+        let info = AccountInfo {
+            address,
+            private_key: eth_private_key,
+            token_name,
+        };
+
+        Ok(Self::from_info(monitor, &info, web3_url).await)
+    }
+
+    /// Derives one wallet per index in `account_indices` from a single `mnemonic`.
+    pub async fn derive_wallets(
+        mnemonic: &str,
+        account_indices: Range<u32>,
+        token_name: TokenLike,
+        monitor: Monitor,
+        web3_url: &str,
+    ) -> anyhow::Result<Vec<Self>> {
+        let mut wallets = Vec::with_capacity(account_indices.len());
+        for account_index in account_indices {
+            let wallet = Self::from_mnemonic(
+                mnemonic,
+                account_index,
+                token_name.clone(),
+                monitor.clone(),
+                web3_url,
+            )
+            .await?;
+            wallets.push(wallet);
+        }
+
+        Ok(wallets)
+    }
+}
+
+impl<S: EthereumSigner> TestWallet<S> {
+    /// Creates a wallet driven by an externally-provided signer, e.g. a Ledger-backed
+    /// one, instead of an in-memory private key.
+    pub async fn from_signer(
+        signer: S,
+        address: Address,
+        token_name: TokenLike,
+        monitor: Monitor,
+        web3_url: &str,
+    ) -> Result<Self, ClientError> {
+        let credentials =
+            WalletCredentials::from_eth_signer(address, signer, Network::Localhost).await?;
+
+        let inner = Wallet::new(monitor.provider.clone(), credentials).await?;
+
+        Ok(Self::from_wallet(token_name, monitor, inner, web3_url).await)
+    }
+
     async fn from_wallet(
         token_name: TokenLike,
         monitor: Monitor,
-        inner: Wallet<PrivateKeySigner, RpcProvider>,
+        inner: Wallet<S, RpcProvider>,
         web3_url: impl AsRef<str>,
     ) -> Self {
         let eth_provider = inner.ethereum(web3_url).await.unwrap();
-        let zk_nonce = inner
-            .provider
-            .account_info(inner.address())
-            .await
-            .unwrap()
-            .committed
-            .nonce;
 
         monitor
             .api_data_pool
@@ -95,27 +183,110 @@ impl TestWallet {
             monitor,
             inner,
             eth_provider,
-            nonce: AtomicU32::new(*zk_nonce),
+            nonce: NonceManager::default(),
+            fee_strategy: Box::new(FixedMultiplierStrategy::default()),
             token_name,
         }
     }
 
+    /// Overrides the fee strategy consulted by `sufficient_fee`, `sign_withdraw_with_strategy`
+    /// and `sign_transfer_with_strategy`. Defaults to [`FixedMultiplierStrategy`], preserving
+    /// the historical behavior.
+    pub fn set_fee_strategy(&mut self, fee_strategy: impl FeeStrategy + 'static) {
+        self.fee_strategy = Box::new(fee_strategy);
+    }
+
     /// Sets the correct nonce from the zkSync network.
     ///
-    /// This method fixes further "nonce mismatch" errors.
+    /// This method fixes further "nonce mismatch" errors. Kept around as an explicit
+    /// escape hatch; the nonce manager resets itself automatically when a submission
+    /// reports a stale nonce, see [`submit_with_retry`](Self::submit_with_retry).
     pub async fn refresh_nonce(&self) -> Result<(), ClientError> {
-        let zk_nonce = self
-            .inner
-            .provider
-            .account_info(self.address())
-            .await?
-            .committed
-            .nonce;
+        self.nonce.reset(&self.inner.provider, self.address()).await
+    }
 
-        self.nonce.store(*zk_nonce, Ordering::SeqCst);
+    /// Returns the account nonce at the given block finality status, mirroring how
+    /// `balance` already lets callers distinguish committed from verified state. This
+    /// lets tests assert that a wallet's verified nonce lags its committed one during
+    /// load, or detect a stuck priority operation by comparing the two.
+    pub async fn nonce_at(&self, status: BlockStatus) -> Result<Nonce, ClientError> {
+        let account_info = self.inner.provider.account_info(self.address()).await?;
+
+        Ok(match status {
+            BlockStatus::Committed => account_info.committed.nonce,
+            BlockStatus::Verified => account_info.verified.nonce,
+        })
+    }
+
+    /// Like `refresh_nonce`, but seeds the nonce manager from the nonce at `status`
+    /// instead of always using the committed nonce.
+    pub async fn refresh_nonce_at(&self, status: BlockStatus) -> Result<(), ClientError> {
+        let nonce = self.nonce_at(status).await?;
+        self.nonce.set(nonce).await;
         Ok(())
     }
 
+    /// Signs via `sign` and submits the result. If the send fails because the nonce
+    /// it used was stale (too low or already used), resets the nonce manager from the
+    /// network and calls `sign` again so the retry carries a fresh nonce instead of
+    /// replaying the same one. Any other send failure returns the reserved nonce to
+    /// the free-list before propagating the error, so a single rejected or timed-out
+    /// transaction doesn't permanently skip a nonce.
+    async fn submit_with_retry<F, Fut>(&self, sign: F) -> Result<TxHash, ClientError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<
+            Output = Result<(ZkSyncTx, Option<PackedEthSignature>), ClientError>,
+        >,
+    {
+        let (tx, eth_signature) = sign().await?;
+        let nonce = *tx.nonce();
+
+        match self.monitor.provider.send_tx(tx, eth_signature).await {
+            Ok(tx_hash) => Ok(tx_hash),
+            Err(err) if NonceManager::is_stale_nonce_error(&err) => {
+                self.nonce.reset(&self.inner.provider, self.address()).await?;
+                let (tx, eth_signature) = sign().await?;
+                self.monitor.provider.send_tx(tx, eth_signature).await
+            }
+            Err(err) => {
+                self.nonce.return_nonce(nonce).await;
+                Err(err)
+            }
+        }
+    }
+
+    /// Signs and submits a transfer, transparently resetting the nonce manager and
+    /// re-signing once if the network reports that the nonce used was stale.
+    pub async fn submit_transfer(
+        &self,
+        to: Address,
+        amount: BigUint,
+        fee: BigUint,
+    ) -> Result<TxHash, ClientError> {
+        self.submit_with_retry(|| self.sign_transfer(to, amount.clone(), fee.clone()))
+            .await
+    }
+
+    /// Signs and submits a withdrawal, transparently resetting the nonce manager and
+    /// re-signing once if the network reports that the nonce used was stale.
+    pub async fn submit_withdraw(
+        &self,
+        amount: BigUint,
+        fee: BigUint,
+    ) -> Result<TxHash, ClientError> {
+        self.submit_with_retry(|| self.sign_withdraw(amount.clone(), fee.clone()))
+            .await
+    }
+
+    /// Signs and submits a change-pubkey transaction, transparently resetting the
+    /// nonce manager and re-signing once if the network reports that the nonce used
+    /// was stale.
+    pub async fn submit_change_pubkey(&self, fee: BigUint) -> Result<TxHash, ClientError> {
+        self.submit_with_retry(|| self.sign_change_pubkey(fee.clone()))
+            .await
+    }
+
     /// Returns the wallet address.
     pub fn address(&self) -> Address {
         self.inner.address()
@@ -123,19 +294,20 @@ impl TestWallet {
 
     /// Returns sufficient fee required to process each kind of transactions in zkSync network.
     pub async fn sufficient_fee(&self) -> Result<BigUint, ClientError> {
-        let fee = self
+        self.quoted_fee(TxFeeTypes::FastWithdraw).await
+    }
+
+    /// Quotes `fee_type` from the network and passes it through the configured
+    /// [`FeeStrategy`] to get the final, packable fee.
+    async fn quoted_fee(&self, fee_type: TxFeeTypes) -> Result<BigUint, ClientError> {
+        let total_fee = self
             .monitor
             .provider
-            .get_tx_fee(
-                TxFeeTypes::FastWithdraw,
-                Address::zero(),
-                self.token_name.clone(),
-            )
+            .get_tx_fee(fee_type, Address::zero(), self.token_name.clone())
             .await?
-            .total_fee
-            * BigUint::from(Self::FEE_FACTOR);
+            .total_fee;
 
-        Ok(closest_packable_fee_amount(&fee))
+        Ok(self.fee_strategy.compute_fee(&total_fee))
     }
 
     /// Returns the wallet balance in zkSync network.
@@ -210,10 +382,11 @@ impl TestWallet {
         &self,
         fee: impl Into<BigUint>,
     ) -> Result<(ZkSyncTx, Option<PackedEthSignature>), ClientError> {
+        let nonce = self.pending_nonce().await?;
         let tx = self
             .inner
             .start_change_pubkey()
-            .nonce(self.pending_nonce())
+            .nonce(nonce)
             .fee_token(self.token_name.clone())?
             .fee(fee)
             .tx()
@@ -228,9 +401,10 @@ impl TestWallet {
         amount: impl Into<BigUint>,
         fee: impl Into<BigUint>,
     ) -> Result<(ZkSyncTx, Option<PackedEthSignature>), ClientError> {
+        let nonce = self.pending_nonce().await?;
         self.inner
             .start_withdraw()
-            .nonce(self.pending_nonce())
+            .nonce(nonce)
             .token(self.token_name.clone())?
             .amount(amount)
             .fee(fee)
@@ -239,6 +413,16 @@ impl TestWallet {
             .await
     }
 
+    // Like `sign_withdraw`, but quotes the fee from the configured `FeeStrategy`
+    // instead of taking one explicitly.
+    pub async fn sign_withdraw_with_strategy(
+        &self,
+        amount: impl Into<BigUint>,
+    ) -> Result<(ZkSyncTx, Option<PackedEthSignature>), ClientError> {
+        let fee = self.quoted_fee(TxFeeTypes::Withdraw).await?;
+        self.sign_withdraw(amount, fee).await
+    }
+
     // Creates a signed transfer tx to a given receiver.
     pub async fn sign_transfer(
         &self,
@@ -246,9 +430,10 @@ impl TestWallet {
         amount: impl Into<BigUint>,
         fee: BigUint,
     ) -> Result<(ZkSyncTx, Option<PackedEthSignature>), ClientError> {
+        let nonce = self.pending_nonce().await?;
         self.inner
             .start_transfer()
-            .nonce(self.pending_nonce())
+            .nonce(nonce)
             .token(self.token_name.clone())?
             .amount(amount)
             .fee(fee)
@@ -257,6 +442,90 @@ impl TestWallet {
             .await
     }
 
+    // Like `sign_transfer`, but quotes the fee from the configured `FeeStrategy`
+    // instead of taking one explicitly.
+    pub async fn sign_transfer_with_strategy(
+        &self,
+        to: impl Into<Address>,
+        amount: impl Into<BigUint>,
+    ) -> Result<(ZkSyncTx, Option<PackedEthSignature>), ClientError> {
+        let fee = self.quoted_fee(TxFeeTypes::Transfer).await?;
+        self.sign_transfer(to, amount, fee).await
+    }
+
+    /// Reserves a contiguous nonce range and signs a transfer per `(address, amount)`
+    /// pair in `outs` concurrently, ready to be handed to
+    /// [`submit_batch`](Self::submit_batch) for pipelined submission.
+    pub async fn sign_batch_transfers(
+        &self,
+        outs: &[(Address, BigUint)],
+        fee: BigUint,
+    ) -> Result<Vec<(ZkSyncTx, Option<PackedEthSignature>)>, ClientError> {
+        let nonce_range = self
+            .nonce
+            .reserve_range(&self.inner.provider, self.address(), outs.len() as u32)
+            .await?;
+
+        let signing = nonce_range.clone().zip(outs.iter()).map(|(nonce, (to, amount))| {
+            let fee = fee.clone();
+            async move {
+                self.inner
+                    .start_transfer()
+                    .nonce(Nonce(nonce))
+                    .token(self.token_name.clone())?
+                    .amount(amount.clone())
+                    .fee(fee)
+                    .to(*to)
+                    .tx()
+                    .await
+            }
+        });
+
+        // If any transfer fails to sign (e.g. an unknown token), none of the reserved
+        // range was actually used — return all of it rather than burning the nonces.
+        match future::try_join_all(signing).await {
+            Ok(txs) => Ok(txs),
+            Err(err) => {
+                self.nonce.return_range(nonce_range).await;
+                Err(err)
+            }
+        }
+    }
+
+    /// Submits a batch produced by
+    /// [`sign_batch_transfers`](Self::sign_batch_transfers), correlating each sent
+    /// transaction with its reserved nonce. If a transaction is rejected, the nonces
+    /// reserved for the remaining (unsent) tail of the batch are returned to the
+    /// free-list instead of being permanently skipped.
+    ///
+    /// Lives on `TestWallet` rather than as a `Monitor`-side entry point: `monitor.rs`
+    /// isn't present in this tree, so pipelining a batch across multiple wallets
+    /// through one `Monitor` is left for whoever owns that file.
+    pub async fn submit_batch(
+        &self,
+        batch: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+    ) -> Result<Vec<TxHash>, ClientError> {
+        let mut tx_hashes = Vec::with_capacity(batch.len());
+
+        let mut txs = batch.into_iter();
+        while let Some((tx, eth_signature)) = txs.next() {
+            let nonce = *tx.nonce();
+            match self.monitor.provider.send_tx(tx, eth_signature).await {
+                Ok(tx_hash) => tx_hashes.push(tx_hash),
+                Err(err) => {
+                    // The batch's nonces are contiguous (reserved via `reserve_range`),
+                    // so the failed transaction's nonce and the unsent tail form a
+                    // single range to hand back to the free-list.
+                    let last_unsent = txs.last().map_or(nonce, |(tx, _)| *tx.nonce());
+                    self.nonce.return_range(nonce..last_unsent + 1).await;
+                    return Err(err);
+                }
+            }
+        }
+
+        Ok(tx_hashes)
+    }
+
     // Deposits tokens from Ethereum to the contract.
     pub async fn deposit(&self, amount: impl Into<BigUint>) -> anyhow::Result<PriorityOp> {
         let eth_tx_hash = self
@@ -291,7 +560,7 @@ impl TestWallet {
     }
 
     /// Returns an underlying wallet.
-    pub fn into_inner(self) -> Wallet<PrivateKeySigner, RpcProvider> {
+    pub fn into_inner(self) -> Wallet<S, RpcProvider> {
         self.inner
     }
 
@@ -322,9 +591,10 @@ impl TestWallet {
         Ok(())
     }
 
-    /// Returns appropriate nonce for the new transaction and increments the nonce.
-    fn pending_nonce(&self) -> Nonce {
-        Nonce(self.nonce.fetch_add(1, Ordering::SeqCst))
+    /// Returns appropriate nonce for the new transaction, reserving it with the
+    /// nonce manager.
+    async fn pending_nonce(&self) -> Result<Nonce, ClientError> {
+        self.nonce.next_nonce(&self.inner.provider, self.address()).await
     }
 }
 